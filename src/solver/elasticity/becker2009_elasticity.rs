@@ -12,7 +12,7 @@ use crate::object::{Boundary, Fluid};
 use crate::solver::NonPressureForce;
 use crate::TimestepManager;
 
-fn elasticity_coefficients(young_modulus: Real, poisson_ratio: Real) -> (Real, Real, Real) {
+pub(crate) fn elasticity_coefficients(young_modulus: Real, poisson_ratio: Real) -> (Real, Real, Real) {
     let _1 = na::one::<Real>();
     let _2: Real = na::convert::<_, Real>(2.0);
 
@@ -24,7 +24,74 @@ fn elasticity_coefficients(young_modulus: Real, poisson_ratio: Real) -> (Real, R
     (d0, d1, d2)
 }
 
-fn sym_mat_mul_vec(mat: &SpatialVector<Real>, v: &Vector<Real>) -> Vector<Real> {
+/// Projects a trial stress back onto the von Mises yield surface (radial return / closest-point
+/// projection), updating the accumulated plastic strain `eps_p` and equivalent plastic strain
+/// `eps_bar` in place. `mu` is the shear modulus. Perfectly plastic materials use
+/// `hardening_modulus == 0`.
+fn von_mises_return_map(
+    sigma_trial: &SpatialVector<Real>,
+    mu: Real,
+    yield_stress: Real,
+    hardening_modulus: Real,
+    eps_p: &mut SpatialVector<Real>,
+    eps_bar: &mut Real,
+) -> SpatialVector<Real> {
+    let _2: Real = na::convert::<_, Real>(2.0);
+    let _3: Real = na::convert::<_, Real>(3.0);
+    let sqrt_2_3 = (_2 / _3).sqrt();
+
+    #[cfg(feature = "dim2")]
+    let mean = (sigma_trial.x + sigma_trial.y) / _2;
+    #[cfg(feature = "dim3")]
+    let mean = (sigma_trial.x + sigma_trial.y + sigma_trial.z) / _3;
+
+    #[cfg(feature = "dim2")]
+    let s = SpatialVector::new(sigma_trial.x - mean, sigma_trial.y - mean, sigma_trial.z);
+    #[cfg(feature = "dim3")]
+    let s = SpatialVector::new(
+        sigma_trial.x - mean,
+        sigma_trial.y - mean,
+        sigma_trial.z - mean,
+        sigma_trial.w,
+        sigma_trial.a,
+        sigma_trial.b,
+    );
+
+    // The off-diagonal terms appear twice in the full symmetric tensor, hence the `_2 * ...`.
+    #[cfg(feature = "dim2")]
+    let s_norm = (s.x * s.x + s.y * s.y + _2 * s.z * s.z).sqrt();
+    #[cfg(feature = "dim3")]
+    let s_norm =
+        (s.x * s.x + s.y * s.y + s.z * s.z + _2 * (s.w * s.w + s.a * s.a + s.b * s.b)).sqrt();
+
+    let f = s_norm - sqrt_2_3 * (yield_stress + hardening_modulus * *eps_bar);
+
+    if f <= na::zero::<Real>() || s_norm <= na::zero::<Real>() {
+        return *sigma_trial;
+    }
+
+    let dgamma = f / (_2 * mu + (_2 / _3) * hardening_modulus);
+    let inv_s_norm = s_norm.recip();
+
+    #[cfg(feature = "dim2")]
+    let n = SpatialVector::new(s.x * inv_s_norm, s.y * inv_s_norm, s.z * inv_s_norm);
+    #[cfg(feature = "dim3")]
+    let n = SpatialVector::new(
+        s.x * inv_s_norm,
+        s.y * inv_s_norm,
+        s.z * inv_s_norm,
+        s.w * inv_s_norm,
+        s.a * inv_s_norm,
+        s.b * inv_s_norm,
+    );
+
+    *eps_p += n * dgamma;
+    *eps_bar += sqrt_2_3 * dgamma;
+
+    *sigma_trial - n * (dgamma * _2 * mu)
+}
+
+pub(crate) fn sym_mat_mul_vec(mat: &SpatialVector<Real>, v: &Vector<Real>) -> Vector<Real> {
     #[cfg(feature = "dim2")]
     return Vector::new(mat.x * v.x + mat.z * v.y, mat.z * v.x + mat.y * v.y);
 
@@ -36,6 +103,62 @@ fn sym_mat_mul_vec(mat: &SpatialVector<Real>, v: &Vector<Real>) -> Vector<Real>
     );
 }
 
+/// A finite-strain hyperelastic material model, selectable as an alternative to the
+/// linearized/corotational strain measure for large rotations and stretches.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HyperelasticModel {
+    /// St. Venant–Kirchhoff: `S = lambda*tr(E)*I + 2*mu*E`, with Green-Lagrange strain
+    /// `E = 0.5*(C - I)`.
+    StVenantKirchhoff,
+    /// Compressible Neo-Hookean: `S = mu*(I - C_inv) + lambda*ln(J)*C_inv`.
+    NeoHookean,
+}
+
+/// Computes the second Piola-Kirchhoff stress of `model` from the deformation gradient `f`,
+/// flattened into the symmetric `SpatialVector` layout used by the force-assembly loop.
+///
+/// Returns a zero stress for inverted elements (`det(f) <= 0`), where the model is undefined.
+fn hyperelastic_stress(
+    model: HyperelasticModel,
+    f: &Matrix<Real>,
+    lambda: Real,
+    mu: Real,
+) -> SpatialVector<Real> {
+    let _0 = na::zero::<Real>();
+    let _0_5: Real = na::convert::<_, Real>(0.5);
+    let _2: Real = na::convert::<_, Real>(2.0);
+
+    let c_mat = f.transpose() * f;
+    let j = f.determinant();
+
+    if j <= _0 {
+        return SpatialVector::zeros();
+    }
+
+    let s = match model {
+        HyperelasticModel::StVenantKirchhoff => {
+            let e = (c_mat - Matrix::identity()) * _0_5;
+            Matrix::identity() * (lambda * e.trace()) + e * (mu * _2)
+        }
+        HyperelasticModel::NeoHookean => match c_mat.try_inverse() {
+            Some(c_inv) => (Matrix::identity() - c_inv) * mu + c_inv * (lambda * j.ln()),
+            None => return SpatialVector::zeros(),
+        },
+    };
+
+    #[cfg(feature = "dim2")]
+    return SpatialVector::new(s.m11, s.m22, (s.m12 + s.m21) * _0_5);
+    #[cfg(feature = "dim3")]
+    return SpatialVector::new(
+        s.m11,
+        s.m22,
+        s.m33,
+        (s.m12 + s.m21) * _0_5,
+        (s.m13 + s.m31) * _0_5,
+        (s.m23 + s.m32) * _0_5,
+    );
+}
+
 // https://cg.informatik.uni-freiburg.de/publications/2009_NP_corotatedSPH.pdf
 /// Elasticity based on the method from Becker et al. 2009.
 pub struct Becker2009Elasticity<
@@ -46,12 +169,19 @@ pub struct Becker2009Elasticity<
     d1: Real,
     d2: Real,
     nonlinear_strain: bool,
+    yield_stress: Option<Real>,
+    hardening_modulus: Real,
+    material_model: Option<HyperelasticModel>,
+    biot_alpha: Option<Real>,
+    pore_pressure_stiffness: Real,
     volumes0: Vec<Real>,
     positions0: Vec<Point<Real>>,
     contacts0: ParticlesContacts,
     rotations: Vec<RotationMatrix<Real>>,
     deformation_gradient_tr: Vec<Matrix<Real>>,
     stress: Vec<SpatialVector<Real>>,
+    eps_p: Vec<SpatialVector<Real>>,
+    eps_bar: Vec<Real>,
     phantom: PhantomData<(KernelDensity, KernelGradient)>,
 }
 
@@ -71,16 +201,82 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel>
             d1,
             d2,
             nonlinear_strain,
+            yield_stress: None,
+            hardening_modulus: na::zero(),
+            material_model: None,
+            biot_alpha: None,
+            pore_pressure_stiffness: na::zero(),
             volumes0: Vec::new(),
             positions0: Vec::new(),
             contacts0: ParticlesContacts::new(),
             rotations: Vec::new(),
             deformation_gradient_tr: Vec::new(),
             stress: Vec::new(),
+            eps_p: Vec::new(),
+            eps_bar: Vec::new(),
             phantom: PhantomData,
         }
     }
 
+    /// Initialize elastoplastic elasticity from its young modulus, poisson ratio, and von Mises
+    /// yield parameters.
+    ///
+    /// Once the von Mises equivalent stress (`sqrt(3/2) * ||dev(sigma)||`) exceeds the current
+    /// yield stress (`yield_stress`, raised by hardening), a radial-return (closest-point
+    /// projection) step keeps the material on the yield surface and accumulates permanent
+    /// (plastic) strain instead of letting the stress grow without bound. `hardening_modulus`
+    /// controls how much the yield surface grows with accumulated plastic strain; use `0.0` for
+    /// perfectly plastic behavior.
+    pub fn new_elastoplastic(
+        young_modulus: Real,
+        poisson_ratio: Real,
+        nonlinear_strain: bool,
+        yield_stress: Real,
+        hardening_modulus: Real,
+    ) -> Self {
+        let mut result = Self::new(young_modulus, poisson_ratio, nonlinear_strain);
+        result.yield_stress = Some(yield_stress);
+        result.hardening_modulus = hardening_modulus;
+        result
+    }
+
+    /// Initialize a finite-deformation hyperelastic material (see [`HyperelasticModel`]) from its
+    /// young modulus and poisson ratio.
+    ///
+    /// Unlike the corotational strain measure used by [`Self::new`], this reconstructs the full
+    /// deformation gradient each step, making it suitable for large rotations and stretches
+    /// without a corotation approximation.
+    pub fn new_hyperelastic(
+        young_modulus: Real,
+        poisson_ratio: Real,
+        model: HyperelasticModel,
+    ) -> Self {
+        let mut result = Self::new(young_modulus, poisson_ratio, false);
+        result.material_model = Some(model);
+        result
+    }
+
+    /// Initialize a poro-elastic variant of this elasticity following Biot's effective-stress
+    /// principle: the force-producing stress becomes `sigma_total = sigma_effective - alpha*p*I`,
+    /// where `sigma_effective` is the elastic (or elastoplastic) stress computed as usual and `p`
+    /// is the pore pressure at each particle.
+    ///
+    /// The pore pressure is derived one-way from the fluid density passed to [`NonPressureForce::solve`]
+    /// through a simple equation of state, `p_i = pore_pressure_stiffness * max(density_i/density0 - 1, 0)`,
+    /// so that only a compressed (pressurized) fluid unloads the solid skeleton.
+    pub fn new_poroelastic(
+        young_modulus: Real,
+        poisson_ratio: Real,
+        nonlinear_strain: bool,
+        alpha: Real,
+        pore_pressure_stiffness: Real,
+    ) -> Self {
+        let mut result = Self::new(young_modulus, poisson_ratio, nonlinear_strain);
+        result.biot_alpha = Some(alpha);
+        result.pore_pressure_stiffness = pore_pressure_stiffness;
+        result
+    }
+
     fn init(&mut self, kernel_radius: Real, fluid: &Fluid) {
         let nparticles = fluid.positions.len();
 
@@ -92,6 +288,8 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel>
             self.deformation_gradient_tr
                 .resize(nparticles, Matrix::identity());
             self.stress.resize(nparticles, SpatialVector::zeros());
+            self.eps_p.resize(nparticles, SpatialVector::zeros());
+            self.eps_bar.resize(nparticles, na::zero::<Real>());
             geometry::compute_self_contacts(kernel_radius, fluid, &mut self.contacts0);
 
             for contacts in self.contacts0.contacts_mut() {
@@ -169,12 +367,19 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel>
         let d2 = self.d2;
 
         let nonlinear_strain = self.nonlinear_strain;
+        let yield_stress = self.yield_stress;
+        let hardening_modulus = self.hardening_modulus;
+        let material_model = self.material_model;
+        let lambda = self.d1;
+        let mu = d2;
         let volumes0 = &self.volumes0;
 
         par_iter_mut!(&mut self.deformation_gradient_tr)
             .zip(&mut self.stress)
+            .zip(&mut self.eps_p)
+            .zip(&mut self.eps_bar)
             .enumerate()
-            .for_each(|(i, (deformation_grad_tr, stress))| {
+            .for_each(|(i, (((deformation_grad_tr, stress), eps_p), eps_bar))| {
                 let mut grad_tr = Matrix::zeros();
 
                 for c in contacts0.particle_contacts(i).read().unwrap().iter() {
@@ -186,47 +391,81 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel>
 
                 *deformation_grad_tr = grad_tr;
 
+                if let Some(model) = material_model {
+                    let f = grad_tr.transpose() + Matrix::identity();
+                    *stress = hyperelastic_stress(model, &f, lambda, mu);
+                    return;
+                }
+
                 #[cfg(feature = "dim3")]
                 {
                     if nonlinear_strain {
                         let j = grad_tr + Matrix::identity();
                         let jjt = j * j.transpose();
 
-                        let stress012 = c_top_left
-                            * Vector::new(
-                                jjt.m11 - na::one::<Real>(),
-                                jjt.m22 - na::one::<Real>(),
-                                jjt.m33 - na::one::<Real>(),
-                            )
-                            * _0_5;
-                        *stress = SpatialVector::new(
+                        let eps_total = SpatialVector::new(
+                            (jjt.m11 - na::one::<Real>()) * _0_5,
+                            (jjt.m22 - na::one::<Real>()) * _0_5,
+                            (jjt.m33 - na::one::<Real>()) * _0_5,
+                            jjt.m21 * _0_5,
+                            jjt.m31 * _0_5,
+                            jjt.m32 * _0_5,
+                        );
+                        let eps_e = eps_total - *eps_p;
+
+                        let stress012 = c_top_left * Vector::new(eps_e.x, eps_e.y, eps_e.z);
+                        let sigma_trial = SpatialVector::new(
                             stress012.x,
                             stress012.y,
                             stress012.z,
-                            jjt.m21 * _0_5 * d2,
-                            jjt.m31 * _0_5 * d2,
-                            jjt.m32 * _0_5 * d2,
+                            eps_e.w * d2,
+                            eps_e.a * d2,
+                            eps_e.b * d2,
                         );
+
+                        *stress = match yield_stress {
+                            Some(sigma_y) => von_mises_return_map(
+                                &sigma_trial,
+                                d2,
+                                sigma_y,
+                                hardening_modulus,
+                                eps_p,
+                                eps_bar,
+                            ),
+                            None => sigma_trial,
+                        };
                     } else {
-                        // let strain = Vector::new(
-                        //     grad_tr.m11,
-                        //     grad_tr.m22,
-                        //     grad_tr.m33,
-                        //     (grad_tr.m21 + grad_tr.m12) * _0_5,
-                        //     (grad_tr.m31 + grad_tr.m13) * _0_5,
-                        //     (grad_tr.m23 + grad_tr.m32) * _0_5,
-                        // );
-
-                        let stress012 =
-                            c_top_left * Vector::new(grad_tr.m11, grad_tr.m22, grad_tr.m33);
-                        *stress = SpatialVector::new(
+                        let eps_total = SpatialVector::new(
+                            grad_tr.m11,
+                            grad_tr.m22,
+                            grad_tr.m33,
+                            (grad_tr.m21 + grad_tr.m12) * _0_5,
+                            (grad_tr.m31 + grad_tr.m13) * _0_5,
+                            (grad_tr.m23 + grad_tr.m32) * _0_5,
+                        );
+                        let eps_e = eps_total - *eps_p;
+
+                        let stress012 = c_top_left * Vector::new(eps_e.x, eps_e.y, eps_e.z);
+                        let sigma_trial = SpatialVector::new(
                             stress012.x,
                             stress012.y,
                             stress012.z,
-                            (grad_tr.m21 + grad_tr.m12) * _0_5 * d2,
-                            (grad_tr.m31 + grad_tr.m13) * _0_5 * d2,
-                            (grad_tr.m23 + grad_tr.m32) * _0_5 * d2,
+                            eps_e.w * d2,
+                            eps_e.a * d2,
+                            eps_e.b * d2,
                         );
+
+                        *stress = match yield_stress {
+                            Some(sigma_y) => von_mises_return_map(
+                                &sigma_trial,
+                                d2,
+                                sigma_y,
+                                hardening_modulus,
+                                eps_p,
+                                eps_bar,
+                            ),
+                            None => sigma_trial,
+                        };
                     }
                 }
 
@@ -236,30 +475,110 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel>
                         let j = grad_tr + Matrix::identity();
                         let jjt = j * j.transpose();
 
-                        let stress01 = c_top_left
-                            * Vector::new(jjt.m11 - na::one::<Real>(), jjt.m22 - na::one::<Real>())
-                            * _0_5;
-                        *stress = SpatialVector::new(stress01.x, stress01.y, jjt.m21 * _0_5 * d2);
+                        let eps_total = SpatialVector::new(
+                            (jjt.m11 - na::one::<Real>()) * _0_5,
+                            (jjt.m22 - na::one::<Real>()) * _0_5,
+                            jjt.m21 * _0_5,
+                        );
+                        let eps_e = eps_total - *eps_p;
+
+                        let stress01 = c_top_left * Vector::new(eps_e.x, eps_e.y);
+                        let sigma_trial = SpatialVector::new(stress01.x, stress01.y, eps_e.z * d2);
+
+                        *stress = match yield_stress {
+                            Some(sigma_y) => von_mises_return_map(
+                                &sigma_trial,
+                                d2,
+                                sigma_y,
+                                hardening_modulus,
+                                eps_p,
+                                eps_bar,
+                            ),
+                            None => sigma_trial,
+                        };
                     } else {
-                        // let strain = Vector::new(
-                        //     grad_tr.m11,
-                        //     grad_tr.m22,
-                        //     grad_tr.m33,
-                        //     (grad_tr.m21 + grad_tr.m12) * _0_5,
-                        //     (grad_tr.m31 + grad_tr.m13) * _0_5,
-                        //     (grad_tr.m23 + grad_tr.m32) * _0_5,
-                        // );
-
-                        let stress01 = c_top_left * Vector::new(grad_tr.m11, grad_tr.m22);
-                        *stress = SpatialVector::new(
-                            stress01.x,
-                            stress01.y,
-                            (grad_tr.m21 + grad_tr.m12) * _0_5 * d2,
+                        let eps_total = SpatialVector::new(
+                            grad_tr.m11,
+                            grad_tr.m22,
+                            (grad_tr.m21 + grad_tr.m12) * _0_5,
                         );
+                        let eps_e = eps_total - *eps_p;
+
+                        let stress01 = c_top_left * Vector::new(eps_e.x, eps_e.y);
+                        let sigma_trial =
+                            SpatialVector::new(stress01.x, stress01.y, eps_e.z * d2);
+
+                        *stress = match yield_stress {
+                            Some(sigma_y) => von_mises_return_map(
+                                &sigma_trial,
+                                d2,
+                                sigma_y,
+                                hardening_modulus,
+                                eps_p,
+                                eps_bar,
+                            ),
+                            None => sigma_trial,
+                        };
                     }
                 }
             })
     }
+
+    /// Applies Biot's effective-stress coupling, subtracting `alpha * p_i` from the diagonal of
+    /// each particle's stress so that pore pressure unloads the solid skeleton. The pore pressure
+    /// is clamped at zero, so a fluid below its rest density has no effect. A no-op unless a pore pressure source was configured via
+    /// [`Self::new_poroelastic`].
+    fn apply_pore_pressure(&mut self, densities: &[Real], density0: Real) {
+        let alpha = match self.biot_alpha {
+            Some(alpha) => alpha,
+            None => return,
+        };
+        let stiffness = self.pore_pressure_stiffness;
+        let _0 = na::zero::<Real>();
+        let _1 = na::one::<Real>();
+
+        par_iter_mut!(&mut self.stress)
+            .enumerate()
+            .for_each(|(i, stress)| {
+                let p = (stiffness * (densities[i] / density0 - _1)).max(_0);
+                let correction = alpha * p;
+                stress.x -= correction;
+                stress.y -= correction;
+                #[cfg(feature = "dim3")]
+                {
+                    stress.z -= correction;
+                }
+            });
+    }
+
+    /// Runs `init`, `compute_rotations`, and `compute_stresses` in sequence, after which the
+    /// computed corotational state is available through the accessors below. Exposed so that
+    /// [`super::ViscoelasticRelaxation`] can reuse this machinery instead of duplicating it.
+    pub(crate) fn compute_corotational_stresses(&mut self, kernel_radius: Real, fluid: &Fluid) {
+        self.init(kernel_radius, fluid);
+        self.compute_rotations(kernel_radius, fluid);
+        self.compute_stresses(kernel_radius, fluid);
+    }
+
+    pub(crate) fn contacts0(&self) -> &ParticlesContacts {
+        &self.contacts0
+    }
+
+    pub(crate) fn volumes0(&self) -> &[Real] {
+        &self.volumes0
+    }
+
+    pub(crate) fn rotations(&self) -> &[RotationMatrix<Real>] {
+        &self.rotations
+    }
+
+    pub(crate) fn deformation_gradient_tr(&self) -> &[Matrix<Real>] {
+        &self.deformation_gradient_tr
+    }
+
+    pub(crate) fn stress(&self) -> &[SpatialVector<Real>] {
+        &self.stress
+    }
 }
 
 impl<KernelDensity: Kernel, KernelGradient: Kernel> NonPressureForce
@@ -273,13 +592,14 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel> NonPressureForce
         _fluid_boundaries_contacts: &ParticlesContacts,
         fluid: &mut Fluid,
         _boundaries: &[Boundary],
-        _densities: &[Real],
+        densities: &[Real],
     ) {
         self.init(kernel_radius, fluid);
 
         let _0_5: Real = na::convert::<_, Real>(0.5f64);
         self.compute_rotations(kernel_radius, fluid);
         self.compute_stresses(kernel_radius, fluid);
+        self.apply_pore_pressure(densities, fluid.density0);
 
         // Compute and apply forces.
         let contacts0 = &self.contacts0;
@@ -290,7 +610,10 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel> NonPressureForce
         let volumes = &fluid.volumes;
         let density0 = fluid.density0;
 
-        if self.nonlinear_strain {
+        // The deformation-gradient term turns the 2nd Piola-Kirchhoff stress computed above into
+        // a first Piola-Kirchhoff-like nodal force; it's required both for the quadratic
+        // corotational strain and for the hyperelastic models, which allow `F` far from `I`.
+        if self.nonlinear_strain || self.material_model.is_some() {
             par_iter_mut!(fluid.accelerations)
                 .enumerate()
                 .for_each(|(i, acceleration)| {
@@ -337,6 +660,8 @@ impl<KernelDensity: Kernel, KernelGradient: Kernel> NonPressureForce
         self.volumes0 = crate::z_order::apply_permutation(permutation, &self.volumes0);
         self.positions0 = crate::z_order::apply_permutation(permutation, &self.positions0);
         self.rotations = crate::z_order::apply_permutation(permutation, &self.rotations);
+        self.eps_p = crate::z_order::apply_permutation(permutation, &self.eps_p);
+        self.eps_bar = crate::z_order::apply_permutation(permutation, &self.eps_bar);
         self.contacts0.apply_permutation(permutation);
     }
 }
@@ -0,0 +1,5 @@
+pub use self::becker2009_elasticity::{Becker2009Elasticity, HyperelasticModel};
+pub use self::viscoelastic_relaxation::{PronyTerm, ViscoelasticRelaxation};
+
+mod becker2009_elasticity;
+mod viscoelastic_relaxation;
@@ -0,0 +1,233 @@
+use std::marker::PhantomData;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::geometry::ParticlesContacts;
+use crate::kernel::{CubicSplineKernel, Kernel};
+use crate::math::{Real, SpatialVector, Vector};
+use crate::object::{Boundary, Fluid};
+use crate::solver::NonPressureForce;
+use crate::TimestepManager;
+
+use super::becker2009_elasticity::sym_mat_mul_vec;
+use super::Becker2009Elasticity;
+
+/// A single term of a Prony (generalized Maxwell) series: a relative modulus `gamma` that relaxes
+/// with time constant `tau`.
+#[derive(Copy, Clone, Debug)]
+pub struct PronyTerm {
+    /// Relative modulus of this term, as a fraction of the instantaneous elastic modulus.
+    pub gamma: Real,
+    /// Relaxation time of this term.
+    pub tau: Real,
+}
+
+impl PronyTerm {
+    /// Creates a new Prony term from its relative modulus and relaxation time.
+    pub fn new(gamma: Real, tau: Real) -> Self {
+        Self { gamma, tau }
+    }
+}
+
+/// Exponential-integrator (Simo) update of one internal viscous stress variable.
+fn update_internal_stress(
+    dt: Real,
+    tau: Real,
+    gamma: Real,
+    h_prev: SpatialVector<Real>,
+    stress_increment: SpatialVector<Real>,
+) -> SpatialVector<Real> {
+    let _1 = na::one::<Real>();
+    let eps: Real = na::convert::<_, Real>(1.0e-6);
+    let x = dt / tau;
+
+    if x <= eps {
+        return h_prev + stress_increment * gamma;
+    }
+
+    let decay = (-x).exp();
+    let ramp = gamma * (_1 - decay) / x;
+    h_prev * decay + stress_increment * ramp
+}
+
+/// Viscoelastic stress relaxation (generalized Maxwell / Prony series) wrapping the corotational
+/// elasticity of [`Becker2009Elasticity`].
+///
+/// The instantaneous elastic stress is computed exactly like `Becker2009Elasticity`, then relaxed
+/// towards `gamma_inf` of its value through one or more Prony terms, allowing materials ranging
+/// from purely elastic (`prony` empty) to strongly dissipative (gels, dough, soft tissue).
+pub struct ViscoelasticRelaxation<
+    KernelDensity: Kernel = CubicSplineKernel,
+    KernelGradient: Kernel = CubicSplineKernel,
+> {
+    nonlinear_strain: bool,
+    prony: Vec<PronyTerm>,
+    gamma_inf: Real,
+    elastic: Becker2009Elasticity<KernelDensity, KernelGradient>,
+    /// `S_n`: the instantaneous elastic stress computed at the previous step.
+    stress_prev: Vec<SpatialVector<Real>>,
+    /// `h_k`: one internal viscous stress history per Prony term, per particle.
+    h: Vec<Vec<SpatialVector<Real>>>,
+    phantom: PhantomData<(KernelDensity, KernelGradient)>,
+}
+
+impl<KernelDensity: Kernel, KernelGradient: Kernel>
+    ViscoelasticRelaxation<KernelDensity, KernelGradient>
+{
+    /// Initialize viscoelastic relaxation from its young modulus, poisson ratio, and Prony series.
+    ///
+    /// If `nonlinear_strain` is `true`, the nonlinear version of the strain tensor is used, like
+    /// in `Becker2009Elasticity`. `prony` lists the `(gamma_k, tau_k)` pairs of the generalized
+    /// Maxwell model; their `gamma_k` must sum to no more than `1.0`, the remainder being the
+    /// long-term (fully relaxed) modulus `gamma_inf`.
+    pub fn new(
+        young_modulus: Real,
+        poisson_ratio: Real,
+        nonlinear_strain: bool,
+        prony: Vec<PronyTerm>,
+    ) -> Self {
+        let gamma_sum = prony
+            .iter()
+            .fold(na::zero::<Real>(), |acc, term| acc + term.gamma);
+        debug_assert!(
+            gamma_sum <= na::one::<Real>(),
+            "the Prony series relative moduli must not sum to more than 1.0"
+        );
+        let gamma_inf = na::one::<Real>() - gamma_sum;
+        let h = prony.iter().map(|_| Vec::new()).collect();
+
+        Self {
+            nonlinear_strain,
+            elastic: Becker2009Elasticity::new(young_modulus, poisson_ratio, nonlinear_strain),
+            prony,
+            gamma_inf,
+            stress_prev: Vec::new(),
+            h,
+            phantom: PhantomData,
+        }
+    }
+
+    fn init(&mut self, fluid: &Fluid) {
+        let nparticles = fluid.positions.len();
+
+        if self.stress_prev.len() != nparticles {
+            self.stress_prev.resize(nparticles, SpatialVector::zeros());
+            for h_k in &mut self.h {
+                h_k.resize(nparticles, SpatialVector::zeros());
+            }
+        }
+    }
+
+    /// Relaxes the instantaneous elastic stress `S_{n+1}` towards the Prony-weighted total stress
+    /// `sigma`, updating the internal viscous stress histories `h_k` and the previous elastic
+    /// stress `S_n` in place.
+    fn relax_stresses(
+        &mut self,
+        stress: &[SpatialVector<Real>],
+        dt: Real,
+    ) -> Vec<SpatialVector<Real>> {
+        let prony = &self.prony;
+        let gamma_inf = self.gamma_inf;
+        let stress_prev = &mut self.stress_prev;
+        let h = &mut self.h;
+
+        let nparticles = stress.len();
+        let mut sigma = vec![SpatialVector::zeros(); nparticles];
+
+        for i in 0..nparticles {
+            let s_increment = stress[i] - stress_prev[i];
+            let mut total = stress[i] * gamma_inf;
+
+            for (term, h_k) in prony.iter().zip(h.iter_mut()) {
+                h_k[i] = update_internal_stress(dt, term.tau, term.gamma, h_k[i], s_increment);
+                total += h_k[i];
+            }
+
+            sigma[i] = total;
+            stress_prev[i] = stress[i];
+        }
+
+        sigma
+    }
+}
+
+impl<KernelDensity: Kernel, KernelGradient: Kernel> NonPressureForce
+    for ViscoelasticRelaxation<KernelDensity, KernelGradient>
+{
+    fn solve(
+        &mut self,
+        timestep: &TimestepManager,
+        kernel_radius: Real,
+        _fluid_fluid_contacts: &ParticlesContacts,
+        _fluid_boundaries_contacts: &ParticlesContacts,
+        fluid: &mut Fluid,
+        _boundaries: &[Boundary],
+        _densities: &[Real],
+    ) {
+        self.init(fluid);
+        self.elastic.compute_corotational_stresses(kernel_radius, fluid);
+
+        let stress = self.elastic.stress().to_vec();
+        let sigma = self.relax_stresses(&stress, timestep.dt());
+
+        // Compute and apply forces.
+        let _0_5: Real = na::convert::<_, Real>(0.5f64);
+        let contacts0 = self.elastic.contacts0();
+        let volumes0 = self.elastic.volumes0();
+        let deformation_gradient_tr = self.elastic.deformation_gradient_tr();
+        let rotations = self.elastic.rotations();
+        let volumes = &fluid.volumes;
+        let density0 = fluid.density0;
+
+        if self.nonlinear_strain {
+            par_iter_mut!(fluid.accelerations)
+                .enumerate()
+                .for_each(|(i, acceleration)| {
+                    for c in contacts0.particle_contacts(i).read().unwrap().iter() {
+                        let mut force = Vector::zeros();
+
+                        let grad_tr_i = &deformation_gradient_tr[c.i];
+                        let d_ij = c.gradient * volumes0[c.j];
+                        let sigma_d_ij = sym_mat_mul_vec(&sigma[c.i], &d_ij);
+                        let f_ji = (sigma_d_ij + grad_tr_i * sigma_d_ij) * -volumes0[c.i];
+
+                        let grad_tr_j = &deformation_gradient_tr[c.j];
+                        let d_ji = c.gradient * (-volumes0[c.i]);
+                        let sigma_d_ji = sym_mat_mul_vec(&sigma[c.j], &d_ji);
+                        let f_ij = (sigma_d_ji + grad_tr_j * sigma_d_ji) * -volumes0[c.j];
+
+                        force += (rotations[c.j] * f_ij - (rotations[c.i] * f_ji)) * _0_5;
+
+                        *acceleration += force / (volumes[i] * density0);
+                    }
+                })
+        } else {
+            par_iter_mut!(fluid.accelerations)
+                .enumerate()
+                .for_each(|(i, acceleration)| {
+                    for c in contacts0.particle_contacts(i).read().unwrap().iter() {
+                        let mut force = Vector::zeros();
+
+                        let d_ij = c.gradient * volumes0[c.j];
+                        let f_ji = sym_mat_mul_vec(&sigma[c.i], &d_ij) * -volumes0[c.i];
+
+                        let d_ji = c.gradient * (-volumes0[c.i]);
+                        let f_ij = sym_mat_mul_vec(&sigma[c.j], &d_ji) * -volumes0[c.j];
+
+                        force += (rotations[c.j] * f_ij - (rotations[c.i] * f_ji)) * _0_5;
+
+                        *acceleration += force / (volumes[i] * density0);
+                    }
+                })
+        }
+    }
+
+    fn apply_permutation(&mut self, permutation: &[usize]) {
+        self.elastic.apply_permutation(permutation);
+        self.stress_prev = crate::z_order::apply_permutation(permutation, &self.stress_prev);
+        for h_k in &mut self.h {
+            *h_k = crate::z_order::apply_permutation(permutation, h_k);
+        }
+    }
+}